@@ -1,20 +1,31 @@
 use axum::body::{Body, Bytes};
 use axum::extract::rejection::JsonRejection;
 use axum::extract::{Multipart, Path, Query, Request, State};
-use axum::middleware::{Next, from_fn, map_request};
+use axum::middleware::{Next, from_fn, from_fn_with_state, map_request};
 use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
-use axum::{Form, Json, Router, serve, Extension};
+use axum::{Form, Json, Router, serve, Extension, RequestExt};
+use axum_extra::TypedHeader;
 use axum_extra::extract::CookieJar;
 use axum_extra::extract::cookie::Cookie;
+use axum_extra::headers::{self, Header, HeaderName, HeaderValue};
 use axum_test::TestServer;
 use axum_test::multipart::{MultipartForm, Part};
+use http::header::ALLOW;
 use http::{HeaderMap, Method, StatusCode, Uri};
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use sqlx::sqlite::SqlitePoolOptions;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 use std::collections::HashMap;
 use std::sync::Arc;
 use axum::error_handling::HandleError;
 use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tokio::sync::RwLock;
 
 #[tokio::main]
 async fn main() {
@@ -411,31 +422,13 @@ async fn test_middleware() {
     response.assert_text("Hello GET 123456");
 }
 
-struct AppError {
-    code: i32,
-    message: String,
-}
-
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        (
-            StatusCode::from_u16(self.code as u16).unwrap(),
-            self.message,
-        )
-            .into_response()
-    }
-}
-
 #[tokio::test]
 async fn test_error_handling() {
-    async fn hello_world(method: Method) -> Result<String, AppError> {
+    async fn hello_world(method: Method) -> Result<String, ApiError> {
         if method == Method::POST {
             Ok("OK".to_string())
         } else {
-            Err (AppError{
-                code :400,
-                message: "Bad Request".to_string(),
-            })
+            Err(ApiError::Validation(vec!["Bad Request".to_string()]))
         }
 
     }
@@ -446,7 +439,10 @@ async fn test_error_handling() {
     let server = TestServer::new(app).unwrap();
     let response = server.get("/get").await;
     response.assert_status(StatusCode::BAD_REQUEST);
-    response.assert_text("Bad Request");
+    response.assert_json(&ApiErrorBody {
+        error: "Validation Error".to_string(),
+        details: vec!["Bad Request".to_string()],
+    });
 
     let response = server.post("/post").await;
     response.assert_status(StatusCode::OK);
@@ -651,4 +647,755 @@ async fn test_fallback() {
     response.assert_status(StatusCode::METHOD_NOT_ALLOWED);
     response.assert_text("Page /first is not found");
 
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    exp: i64,
+}
+
+const JWT_SECRET: &[u8] = b"belajar-rust-axum-secret";
+
+fn jwt_signature(header_and_payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(JWT_SECRET).expect("HMAC accepts keys of any length");
+    mac.update(header_and_payload.as_bytes());
+    URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+}
+
+fn issue_jwt(claims: &Claims) -> String {
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims).unwrap());
+    let signature = jwt_signature(&format!("{}.{}", header, payload));
+    format!("{}.{}.{}", header, payload, signature)
+}
+
+#[derive(Debug, Clone)]
+struct Jwt(Claims);
+
+impl Header for Jwt {
+    fn name() -> &'static HeaderName {
+        static NAME: HeaderName = HeaderName::from_static("authorization");
+        &NAME
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(headers::Error::invalid)?;
+        let value = value.to_str().map_err(|_| headers::Error::invalid())?;
+        let token = value
+            .strip_prefix("Bearer ")
+            .ok_or_else(headers::Error::invalid)?;
+
+        let mut segments = token.split('.');
+        let header = segments.next().ok_or_else(headers::Error::invalid)?;
+        let payload = segments.next().ok_or_else(headers::Error::invalid)?;
+        let signature = segments.next().ok_or_else(headers::Error::invalid)?;
+        if segments.next().is_some() {
+            return Err(headers::Error::invalid());
+        }
+
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| headers::Error::invalid())?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(JWT_SECRET)
+            .expect("HMAC accepts keys of any length");
+        mac.update(format!("{}.{}", header, payload).as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| headers::Error::invalid())?;
+
+        let payload = URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|_| headers::Error::invalid())?;
+        let claims: Claims =
+            serde_json::from_slice(&payload).map_err(|_| headers::Error::invalid())?;
+
+        if claims.exp <= now() {
+            return Err(headers::Error::invalid());
+        }
+
+        Ok(Jwt(claims))
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let token = issue_jwt(&self.0);
+        if let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", token)) {
+            values.extend(std::iter::once(value));
+        }
+    }
+}
+
+async fn auth_middleware(mut request: Request, next: Next) -> Result<Response, ApiError> {
+    let TypedHeader(jwt) = request
+        .extract_parts::<TypedHeader<Jwt>>()
+        .await
+        .map_err(|_| ApiError::Unauthorized)?;
+
+    request.extensions_mut().insert(jwt);
+    Ok(next.run(request).await)
+}
+
+#[tokio::test]
+async fn test_auth_middleware_missing_token() {
+    async fn hello_world(Extension(jwt): Extension<Jwt>) -> String {
+        format!("Hello {}", jwt.0.sub)
+    }
+
+    let app = Router::new()
+        .route("/get", get(hello_world))
+        .layer(from_fn(auth_middleware));
+
+    let server = TestServer::new(app).unwrap();
+    let response = server.get("/get").await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    response.assert_json(&ApiErrorBody {
+        error: "Unauthorized".to_string(),
+        details: vec![],
+    });
+}
+
+#[tokio::test]
+async fn test_auth_middleware_with_token() {
+    async fn hello_world(Extension(jwt): Extension<Jwt>) -> String {
+        format!("Hello {}", jwt.0.sub)
+    }
+
+    let app = Router::new()
+        .route("/get", get(hello_world))
+        .layer(from_fn(auth_middleware));
+
+    let token = issue_jwt(&Claims {
+        sub: "rahasia".to_string(),
+        exp: now() + 3600,
+    });
+
+    let server = TestServer::new(app).unwrap();
+    let response = server
+        .get("/get")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_ok();
+    response.assert_text("Hello rahasia");
+}
+
+#[tokio::test]
+async fn test_auth_middleware_rejects_expired_token() {
+    async fn hello_world(Extension(jwt): Extension<Jwt>) -> String {
+        format!("Hello {}", jwt.0.sub)
+    }
+
+    let app = Router::new()
+        .route("/get", get(hello_world))
+        .layer(from_fn(auth_middleware));
+
+    let token = issue_jwt(&Claims {
+        sub: "rahasia".to_string(),
+        exp: now() - 3600,
+    });
+
+    let server = TestServer::new(app).unwrap();
+    let response = server
+        .get("/get")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_auth_middleware_rejects_tampered_token() {
+    async fn hello_world(Extension(jwt): Extension<Jwt>) -> String {
+        format!("Hello {}", jwt.0.sub)
+    }
+
+    let app = Router::new()
+        .route("/get", get(hello_world))
+        .layer(from_fn(auth_middleware));
+
+    let token = issue_jwt(&Claims {
+        sub: "rahasia".to_string(),
+        exp: now() + 3600,
+    });
+    let segments: Vec<&str> = token.split('.').collect();
+    let forged_payload = URL_SAFE_NO_PAD.encode(
+        serde_json::to_vec(&Claims {
+            sub: "admin".to_string(),
+            exp: now() + 3600,
+        })
+        .unwrap(),
+    );
+    let tampered = format!("{}.{}.{}", segments[0], forged_payload, segments[2]);
+
+    let server = TestServer::new(app).unwrap();
+    let response = server
+        .get("/get")
+        .add_header("Authorization", format!("Bearer {}", tampered))
+        .await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+}
+
+struct AppState {
+    counter: i32,
+}
+
+async fn get_counter(State(state): State<Arc<RwLock<AppState>>>) -> String {
+    let state = state.read().await;
+    format!("Counter {}", state.counter)
+}
+
+async fn increment_counter(State(state): State<Arc<RwLock<AppState>>>) -> String {
+    let mut state = state.write().await;
+    state.counter += 1;
+    format!("Counter {}", state.counter)
+}
+
+#[tokio::test]
+async fn test_state_rwlock() {
+    let state = Arc::new(RwLock::new(AppState { counter: 0 }));
+
+    let app = Router::new()
+        .route("/get", get(get_counter))
+        .route("/post", post(increment_counter))
+        .with_state(state);
+
+    let server = TestServer::new(app).unwrap();
+
+    let mut handles = Vec::new();
+    for _ in 0..10 {
+        let server = server.clone();
+        handles.push(tokio::spawn(async move {
+            server.post("/post").await;
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    let response = server.get("/get").await;
+    response.assert_status_ok();
+    response.assert_text("Counter 10");
+}
+
+#[derive(Debug, Clone)]
+struct Session {
+    id: String,
+    data: String,
+    expiry: i64,
+}
+
+fn now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+fn new_session_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    format!("session-{}", nanos)
+}
+
+#[derive(Clone)]
+struct SqliteSessionStore {
+    pool: SqlitePool,
+}
+
+impl SqliteSessionStore {
+    fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    async fn migrate(&self) -> Result<(), ApiError> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS sessions (id TEXT PRIMARY KEY, data TEXT NOT NULL, expiry INTEGER NOT NULL)",
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn load(&self, id: &str) -> Result<Option<Session>, ApiError> {
+        let row = sqlx::query_as::<_, (String, String, i64)>(
+            "SELECT id, data, expiry FROM sessions WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(id, data, expiry)| Session { id, data, expiry }))
+    }
+
+    async fn store(&self, session: &Session) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO sessions (id, data, expiry) VALUES (?, ?, ?) \
+             ON CONFLICT(id) DO UPDATE SET data = excluded.data, expiry = excluded.expiry",
+        )
+        .bind(&session.id)
+        .bind(&session.data)
+        .bind(session.expiry)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn destroy(&self, id: &str) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM sessions WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+async fn auth_layer(
+    State(store): State<SqliteSessionStore>,
+    cookie: CookieJar,
+    mut request: Request,
+    next: Next,
+) -> Result<(CookieJar, Response), ApiError> {
+    let existing = match cookie.get("session_id") {
+        Some(cookie) => store.load(cookie.value()).await?,
+        None => None,
+    };
+
+    let (session, cookie) = match existing {
+        Some(session) if session.expiry > now() => (session, cookie),
+        _ => {
+            let session = Session {
+                id: new_session_id(),
+                data: "{}".to_string(),
+                expiry: now() + 3600,
+            };
+            let cookie = cookie.add(Cookie::new("session_id", session.id.clone()));
+            (session, cookie)
+        }
+    };
+
+    let handle = Arc::new(Mutex::new(session));
+    request.extensions_mut().insert(Arc::clone(&handle));
+
+    let response = next.run(request).await;
+
+    let session = handle.lock().await.clone();
+    store.store(&session).await?;
+
+    Ok((cookie, response))
+}
+
+async fn new_memory_session_store() -> SqliteSessionStore {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let store = SqliteSessionStore::new(pool);
+    store.migrate().await.unwrap();
+    store
+}
+
+#[tokio::test]
+async fn test_session_store_crud() {
+    let store = new_memory_session_store().await;
+
+    let session = Session {
+        id: "session-1".to_string(),
+        data: "{\"name\":\"Fadel\"}".to_string(),
+        expiry: now() + 3600,
+    };
+    store.store(&session).await.unwrap();
+
+    let loaded = store.load("session-1").await.unwrap().unwrap();
+    assert_eq!(loaded.data, session.data);
+
+    store.destroy("session-1").await.unwrap();
+    assert!(store.load("session-1").await.unwrap().is_none());
+}
+
+async fn session_handler(Extension(session): Extension<Arc<Mutex<Session>>>) -> String {
+    let session = session.lock().await;
+    format!("Session {}", session.id)
+}
+
+#[tokio::test]
+async fn test_session_layer_creates_session_and_sets_cookie() {
+    let store = new_memory_session_store().await;
+
+    let app = Router::new()
+        .route("/get", get(session_handler))
+        .layer(from_fn_with_state(store.clone(), auth_layer))
+        .with_state(store);
+
+    let server = TestServer::new(app).unwrap();
+    let response = server.get("/get").await;
+    response.assert_status_ok();
+    assert!(response.maybe_cookie("session_id").is_some());
+}
+
+#[tokio::test]
+async fn test_session_layer_reuses_session() {
+    let store = new_memory_session_store().await;
+
+    let app = Router::new()
+        .route("/get", get(session_handler))
+        .layer(from_fn_with_state(store.clone(), auth_layer))
+        .with_state(store);
+
+    let server = TestServer::new(app).unwrap();
+    let first_response = server.get("/get").await;
+    first_response.assert_status_ok();
+    let session_cookie = first_response.cookie("session_id");
+
+    let second_response = server
+        .get("/get")
+        .add_cookie(session_cookie)
+        .await;
+    second_response.assert_status_ok();
+    assert_eq!(first_response.text(), second_response.text());
+}
+
+#[tokio::test]
+async fn test_session_layer_rejects_expired_session() {
+    let store = new_memory_session_store().await;
+    let expired = Session {
+        id: "expired-session".to_string(),
+        data: "{}".to_string(),
+        expiry: now() - 3600,
+    };
+    store.store(&expired).await.unwrap();
+
+    let app = Router::new()
+        .route("/get", get(session_handler))
+        .layer(from_fn_with_state(store.clone(), auth_layer))
+        .with_state(store);
+
+    let server = TestServer::new(app).unwrap();
+    let response = server
+        .get("/get")
+        .add_cookie(Cookie::new("session_id", "expired-session"))
+        .await;
+    response.assert_status_ok();
+    assert_ne!(response.text(), "Session expired-session");
+}
+
+#[derive(Serialize)]
+struct ApiErrorBody {
+    error: String,
+    details: Vec<String>,
+}
+
+enum ApiError {
+    NotFound,
+    Unauthorized,
+    Validation(Vec<String>),
+    MethodNotAllowed(String),
+    Internal(anyhow::Error),
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        ApiError::Internal(error)
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(error: sqlx::Error) -> Self {
+        ApiError::Internal(error.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error, details, allow) = match self {
+            ApiError::NotFound => (StatusCode::NOT_FOUND, "Not Found".to_string(), vec![], None),
+            ApiError::Unauthorized => {
+                (StatusCode::UNAUTHORIZED, "Unauthorized".to_string(), vec![], None)
+            }
+            ApiError::Validation(details) => (
+                StatusCode::BAD_REQUEST,
+                "Validation Error".to_string(),
+                details,
+                None,
+            ),
+            ApiError::MethodNotAllowed(allow) => (
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Method Not Allowed".to_string(),
+                vec![allow.clone()],
+                Some(allow),
+            ),
+            ApiError::Internal(error) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal Server Error".to_string(),
+                vec![error.to_string()],
+                None,
+            ),
+        };
+
+        let body = Json(ApiErrorBody { error, details });
+        match allow {
+            Some(allow) => (status, [(ALLOW, allow)], body).into_response(),
+            None => (status, body).into_response(),
+        }
+    }
+}
+
+async fn handle_api_error(error: anyhow::Error) -> ApiError {
+    ApiError::from(error)
+}
+
+#[tokio::test]
+async fn test_api_error_not_found() {
+    async fn route() -> Result<String, ApiError> {
+        Err(ApiError::NotFound)
+    }
+
+    let app = Router::new().route("/get", get(route));
+
+    let server = TestServer::new(app).unwrap();
+    let response = server.get("/get").await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    response.assert_json(&ApiErrorBody {
+        error: "Not Found".to_string(),
+        details: vec![],
+    });
+}
+
+#[tokio::test]
+async fn test_api_error_unauthorized() {
+    async fn route() -> Result<String, ApiError> {
+        Err(ApiError::Unauthorized)
+    }
+
+    let app = Router::new().route("/get", get(route));
+
+    let server = TestServer::new(app).unwrap();
+    let response = server.get("/get").await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    response.assert_json(&ApiErrorBody {
+        error: "Unauthorized".to_string(),
+        details: vec![],
+    });
+}
+
+#[tokio::test]
+async fn test_api_error_validation() {
+    async fn route() -> Result<String, ApiError> {
+        Err(ApiError::Validation(vec!["username is required".to_string()]))
+    }
+
+    let app = Router::new().route("/get", get(route));
+
+    let server = TestServer::new(app).unwrap();
+    let response = server.get("/get").await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    response.assert_json(&ApiErrorBody {
+        error: "Validation Error".to_string(),
+        details: vec!["username is required".to_string()],
+    });
+}
+
+#[tokio::test]
+async fn test_api_error_internal() {
+    async fn route() -> Result<String, ApiError> {
+        Err(anyhow::Error::msg("Database is down").into())
+    }
+
+    let app = Router::new().route("/get", get(route));
+
+    let server = TestServer::new(app).unwrap();
+    let response = server.get("/get").await;
+    response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    response.assert_json(&ApiErrorBody {
+        error: "Internal Server Error".to_string(),
+        details: vec!["Database is down".to_string()],
+    });
+}
+
+#[tokio::test]
+async fn test_api_error_global_handler() {
+    async fn route(request: Request) -> Result<Response, anyhow::Error> {
+        if request.method() == Method::POST {
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .body(Body::from("OK"))?)
+        } else {
+            Err(anyhow::Error::msg("Bad Request"))
+        }
+    }
+
+    let route_service = tower::service_fn(route);
+
+    let app = Router::new().route_service("/get", HandleError::new(route_service, handle_api_error));
+
+    let server = TestServer::new(app).unwrap();
+    let response = server.get("/get").await;
+    response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    response.assert_json(&ApiErrorBody {
+        error: "Internal Server Error".to_string(),
+        details: vec!["Bad Request".to_string()],
+    });
+}
+
+fn allow<H, T>(methods: impl IntoIterator<Item = Method>, handler: H) -> axum::routing::MethodRouter
+where
+    H: axum::handler::Handler<T, ()> + Clone + Send + Sync + 'static,
+    T: 'static,
+{
+    let methods: Vec<Method> = methods.into_iter().collect();
+    let allow_header = methods
+        .iter()
+        .map(Method::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut router = axum::routing::MethodRouter::new();
+    for method in &methods {
+        router = match *method {
+            Method::GET => router.get(handler.clone()),
+            Method::POST => router.post(handler.clone()),
+            Method::PUT => router.put(handler.clone()),
+            Method::DELETE => router.delete(handler.clone()),
+            Method::PATCH => router.patch(handler.clone()),
+            Method::HEAD => router.head(handler.clone()),
+            Method::OPTIONS => router.options(handler.clone()),
+            Method::TRACE => router.trace(handler.clone()),
+            Method::CONNECT => router.connect(handler.clone()),
+            _ => panic!("allow() does not support method {}", method),
+        };
+    }
+
+    router.fallback(move || {
+        let allow_header = allow_header.clone();
+        async move { ApiError::MethodNotAllowed(allow_header) }
+    })
+}
+
+#[tokio::test]
+async fn test_allow_helper() {
+    async fn hello_world() -> String {
+        "Hello".to_string()
+    }
+
+    let app = Router::new().route("/resource", allow([Method::GET, Method::POST], hello_world));
+
+    let server = TestServer::new(app).unwrap();
+
+    let response = server.get("/resource").await;
+    response.assert_status_ok();
+    response.assert_text("Hello");
+
+    let response = server.post("/resource").await;
+    response.assert_status_ok();
+    response.assert_text("Hello");
+
+    let response = server.put("/resource").await;
+    response.assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    response.assert_header("Allow", "GET, POST");
+    response.assert_json(&ApiErrorBody {
+        error: "Method Not Allowed".to_string(),
+        details: vec!["GET, POST".to_string()],
+    });
+}
+
+#[tokio::test]
+async fn test_allow_helper_serves_head() {
+    async fn hello_world() -> String {
+        "Hello".to_string()
+    }
+
+    let app = Router::new().route("/resource", allow([Method::GET, Method::HEAD], hello_world));
+
+    let server = TestServer::new(app).unwrap();
+
+    let response = server.method(Method::HEAD, "/resource").await;
+    response.assert_status_ok();
+}
+
+struct AppRouter<S> {
+    router: Router<S>,
+    state: S,
+}
+
+impl<S> AppRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn with_state(state: S) -> Self {
+        Self {
+            router: Router::new(),
+            state,
+        }
+    }
+
+    fn route(mut self, path: &str, method_router: axum::routing::MethodRouter<S>) -> Self {
+        self.router = self.router.route(path, method_router);
+        self
+    }
+
+    fn merge(mut self, other: Router<S>) -> Self {
+        self.router = self.router.merge(other);
+        self
+    }
+
+    fn nest(mut self, path: &str, other: Router<S>) -> Self {
+        self.router = self.router.nest(path, other);
+        self
+    }
+
+    fn into_router(self) -> Router<()> {
+        self.router.with_state(self.state)
+    }
+
+    fn into_make_service(self) -> axum::routing::IntoMakeService<Router<()>> {
+        self.into_router().into_make_service()
+    }
+}
+
+impl AppRouter<()> {
+    fn without_state() -> Self {
+        AppRouter::with_state(())
+    }
+}
+
+#[tokio::test]
+async fn test_app_router_with_state() {
+    async fn hello_world(State(database): State<Arc<DatabaseConfig>>) -> String {
+        format!("Total {}", database.total)
+    }
+
+    let nested = Router::new().route("/nested", get(hello_world));
+
+    let app = AppRouter::with_state(Arc::new(DatabaseConfig { total: 100 }))
+        .route("/get", get(hello_world))
+        .nest("/api", nested)
+        .into_router();
+
+    let server = TestServer::new(app).unwrap();
+    let response = server.get("/get").await;
+    response.assert_status_ok();
+    response.assert_text("Total 100");
+
+    let response = server.get("/api/nested").await;
+    response.assert_status_ok();
+    response.assert_text("Total 100");
+}
+
+#[tokio::test]
+async fn test_app_router_without_state() {
+    async fn hello_world() -> String {
+        "Hello, World!".to_string()
+    }
+
+    let app = AppRouter::without_state()
+        .route("/get", get(hello_world))
+        .into_router();
+
+    let server = TestServer::new(app).unwrap();
+    let response = server.get("/get").await;
+    response.assert_status_ok();
+    response.assert_text("Hello, World!");
 }
\ No newline at end of file